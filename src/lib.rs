@@ -0,0 +1,928 @@
+#![deny(warnings, clippy::pedantic)]
+#![warn(rust_2018_idioms)]
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use nosleep::NoSleepType;
+use winit::{
+    dpi::{LogicalPosition, LogicalSize},
+    event::{ElementState, Event, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent},
+    event_loop::{ControlFlow, EventLoopWindowTarget},
+    monitor::MonitorHandle,
+    window::{Window, WindowId},
+};
+
+pub use self::solar::Location;
+
+#[cfg(target_os = "macos")]
+use self::cocoa_backend::Cocoa as PlatformBackend;
+#[cfg(not(target_os = "macos"))]
+use self::softbuffer_backend::Softbuffer as PlatformBackend;
+
+/// Smallest opacity the dimming overlay is allowed to reach, so the screen can
+/// never be made invisible (and thus impossible to dismiss).
+const MIN_ALPHA: f64 = 0.1;
+/// How much `[`/`]` move the overlay opacity per press.
+const ALPHA_STEP: f64 = 0.05;
+/// How often the `auto` schedule re-evaluates the sun's position.
+const CIRCADIAN_INTERVAL: Duration = Duration::from_secs(60);
+/// How often the open windows are reconciled against the connected monitors.
+const MONITOR_SYNC_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Fills a monitor with a solid [`Color`].
+///
+/// Each platform paints a full-screen window differently — AppKit sets the
+/// `NSWindow` background color, while X11/Wayland render into a `softbuffer`
+/// surface — so the blanking, keyboard and no-sleep logic can stay portable.
+trait Backend {
+    /// Builds a borderless, full-screen window covering `monitor` and paints it with `color`.
+    fn build_window(
+        &mut self,
+        event_loop: &EventLoopWindowTarget<()>,
+        color: &Color,
+        monitor: &MonitorHandle,
+    ) -> Window;
+
+    /// Repaints an existing `window` with `color`.
+    fn fill(&mut self, window: &Window, color: &Color);
+
+    /// Drops any per-window state held for `id` once its window is gone.
+    fn forget(&mut self, id: WindowId);
+}
+
+/// The logical geometry a full-screen window must cover to fill `monitor`.
+fn window_geometry(monitor: &MonitorHandle) -> (LogicalPosition<f64>, LogicalSize<f64>) {
+    let scale = monitor.scale_factor();
+    (
+        monitor.position().to_logical(scale),
+        monitor.size().to_logical(scale),
+    )
+}
+
+#[cfg(target_os = "macos")]
+mod cocoa_backend {
+    use cocoa::appkit::{NSColor, NSWindow};
+    use winit::{
+        event_loop::EventLoopWindowTarget,
+        monitor::MonitorHandle,
+        platform::macos::{WindowBuilderExtMacOS, WindowExtMacOS},
+        window::{Window, WindowBuilder, WindowId},
+    };
+
+    use super::{window_geometry, Backend, Color};
+
+    /// Paints by setting the `NSWindow` background color.
+    pub(super) struct Cocoa;
+
+    impl Cocoa {
+        pub(super) fn new() -> Self {
+            Self
+        }
+    }
+
+    impl Backend for Cocoa {
+        fn build_window(
+            &mut self,
+            event_loop: &EventLoopWindowTarget<()>,
+            color: &Color,
+            monitor: &MonitorHandle,
+        ) -> Window {
+            let (position, size) = window_geometry(monitor);
+            let window = WindowBuilder::new()
+                .with_title_hidden(true)
+                .with_titlebar_hidden(true)
+                .with_disallow_hidpi(true)
+                .with_transparent(true)
+                .with_position(position)
+                .with_inner_size(size)
+                .build(event_loop)
+                .unwrap();
+            window.set_cursor_visible(false);
+            window.set_simple_fullscreen(true);
+            self.fill(&window, color);
+            window
+        }
+
+        fn fill(&mut self, window: &Window, color: &Color) {
+            let (red, green, blue, alpha) = color.rgba();
+            let ns_color = unsafe {
+                NSColor::colorWithRed_green_blue_alpha_(cocoa::base::nil, red, green, blue, alpha)
+            };
+            // ALLOWED: cocoa crate exposes `*mut objc::runtime::Object`, therefore using cast would
+            // create a pointer to a pointer. Better to just allow it
+            #[allow(clippy::ptr_as_ptr)]
+            let ns_window = window.ns_window() as cocoa::base::id;
+            unsafe { ns_window.setBackgroundColor_(ns_color) };
+        }
+
+        fn forget(&mut self, _id: WindowId) {}
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod softbuffer_backend {
+    use std::{collections::HashMap, num::NonZeroU32};
+
+    use winit::{
+        event_loop::EventLoopWindowTarget,
+        monitor::MonitorHandle,
+        platform::unix::WindowBuilderExtUnix,
+        window::{Fullscreen, Window, WindowBuilder, WindowId},
+    };
+
+    use super::{window_geometry, Backend, Color};
+
+    /// Paints by blitting a solid color into a per-window `softbuffer` surface.
+    pub(super) struct Softbuffer {
+        context: Option<softbuffer::Context>,
+        surfaces: HashMap<WindowId, softbuffer::Surface>,
+    }
+
+    impl Softbuffer {
+        pub(super) fn new() -> Self {
+            Self {
+                context: None,
+                surfaces: HashMap::new(),
+            }
+        }
+    }
+
+    // ALLOWED: color channels are clamped to `0.0..=1.0`, so scaling to a byte can neither
+    // overflow nor be negative
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn channel(value: f64) -> u32 {
+        (value.clamp(0.0, 1.0) * 255.0).round() as u32
+    }
+
+    impl Backend for Softbuffer {
+        fn build_window(
+            &mut self,
+            event_loop: &EventLoopWindowTarget<()>,
+            color: &Color,
+            monitor: &MonitorHandle,
+        ) -> Window {
+            let (position, size) = window_geometry(monitor);
+            let window = WindowBuilder::new()
+                .with_app_id("blank".to_owned())
+                .with_class("blank".to_owned(), "Blank".to_owned())
+                .with_decorations(false)
+                // No `with_transparent`: the `softbuffer` surface is always opaque
+                // (see `fill`), so dimming is a macOS-only feature.
+                .with_position(position)
+                .with_inner_size(size)
+                .with_fullscreen(Some(Fullscreen::Borderless(None)))
+                .build(event_loop)
+                .unwrap();
+            window.set_cursor_visible(false);
+
+            let context = self
+                .context
+                .get_or_insert_with(|| softbuffer::Context::new(event_loop).unwrap());
+            let surface = softbuffer::Surface::new(context, &window).unwrap();
+            self.surfaces.insert(window.id(), surface);
+            self.fill(&window, color);
+            window
+        }
+
+        fn fill(&mut self, window: &Window, color: &Color) {
+            let Some(surface) = self.surfaces.get_mut(&window.id()) else {
+                return;
+            };
+            let size = window.inner_size();
+            let (Some(width), Some(height)) =
+                (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
+            else {
+                return;
+            };
+            surface.resize(width, height).unwrap();
+
+            // `softbuffer` presents opaque `0x00RRGGBB` pixels — the top byte is
+            // ignored — so the overlay cannot be made translucent here. The `[`/`]`
+            // dimming keybindings therefore have no effect on X11/Wayland; `alpha`
+            // is dropped explicitly rather than silently masked off. Real overlay
+            // transparency would need a compositor-backed surface.
+            let (red, green, blue, _alpha) = color.rgba();
+            let pixel = (channel(red) << 16) | (channel(green) << 8) | channel(blue);
+            let mut buffer = surface.buffer_mut().unwrap();
+            buffer.fill(pixel);
+            buffer.present().unwrap();
+        }
+
+        fn forget(&mut self, id: WindowId) {
+            self.surfaces.remove(&id);
+        }
+    }
+}
+
+mod solar {
+    use chrono::{Datelike, Timelike, Utc};
+
+    /// Neutral day-time temperature, in kelvin.
+    const DAY_TEMPERATURE: u32 = 6500;
+    /// Warm night-time temperature, in kelvin.
+    const NIGHT_TEMPERATURE: u32 = 2700;
+    /// Sun elevation at or above which the full day temperature is used.
+    const DAY_ELEVATION: f64 = 3.0;
+    /// Sun elevation at or below which the full night temperature is used (civil twilight).
+    const NIGHT_ELEVATION: f64 = -6.0;
+
+    /// A geographic location the circadian schedule is computed for.
+    pub struct Location {
+        latitude: f64,
+        longitude: f64,
+    }
+
+    impl Location {
+        /// Parses a `"lat,lon"` pair, returning `None` for malformed or out-of-range input.
+        pub fn parse(raw: &str) -> Option<Self> {
+            let (latitude, longitude) = raw.split_once(',')?;
+            Self::new(
+                latitude.trim().parse().ok()?,
+                longitude.trim().parse().ok()?,
+            )
+        }
+
+        /// Builds a location, rejecting coordinates outside their valid ranges.
+        pub fn new(latitude: f64, longitude: f64) -> Option<Self> {
+            if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) {
+                Some(Self {
+                    latitude,
+                    longitude,
+                })
+            } else {
+                None
+            }
+        }
+
+        /// The color temperature matching the sun's current elevation.
+        pub(super) fn temperature(&self) -> u32 {
+            temperature_from_elevation(self.elevation())
+        }
+
+        /// The sun's elevation above the horizon, in degrees, right now.
+        fn elevation(&self) -> f64 {
+            let now = Utc::now();
+            let solar_time = f64::from(now.hour()) + f64::from(now.minute()) / 60.0;
+            elevation(self.latitude, self.longitude, f64::from(now.ordinal()), solar_time)
+        }
+    }
+
+    /// Maps a sun elevation, in degrees, to a color temperature, clamping outside
+    /// the day/night band and interpolating linearly within it.
+    fn temperature_from_elevation(elevation: f64) -> u32 {
+        if elevation >= DAY_ELEVATION {
+            DAY_TEMPERATURE
+        } else if elevation <= NIGHT_ELEVATION {
+            NIGHT_TEMPERATURE
+        } else {
+            let ratio = (elevation - NIGHT_ELEVATION) / (DAY_ELEVATION - NIGHT_ELEVATION);
+            let temperature = f64::from(NIGHT_TEMPERATURE)
+                + ratio * f64::from(DAY_TEMPERATURE - NIGHT_TEMPERATURE);
+            // ALLOWED: the interpolation stays within `NIGHT_TEMPERATURE..=DAY_TEMPERATURE`,
+            // both of which are small, positive, whole kelvin values
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            {
+                temperature.round() as u32
+            }
+        }
+    }
+
+    /// The sun's elevation above the horizon, in degrees, for a location, a
+    /// day-of-year `N` and a UTC time of day `solar_time` in hours, which is
+    /// shifted by the longitude to local solar time.
+    fn elevation(latitude: f64, longitude: f64, day_of_year: f64, solar_time: f64) -> f64 {
+        let solar_time = solar_time + longitude / 15.0;
+
+        let declination =
+            (23.45 * ((360.0 / 365.0) * (day_of_year - 81.0)).to_radians().sin()).to_radians();
+        let hour_angle = (15.0 * (solar_time - 12.0)).to_radians();
+        let latitude = latitude.to_radians();
+
+        (latitude.sin() * declination.sin()
+            + latitude.cos() * declination.cos() * hour_angle.cos())
+        .asin()
+        .to_degrees()
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::{
+            elevation, temperature_from_elevation, Location, DAY_ELEVATION, DAY_TEMPERATURE,
+            NIGHT_ELEVATION, NIGHT_TEMPERATURE,
+        };
+
+        #[test]
+        fn parse_rejects_out_of_range() {
+            assert!(Location::parse("91,0").is_none());
+            assert!(Location::parse("0,181").is_none());
+            assert!(Location::parse("-90.1,0").is_none());
+            assert!(Location::parse("not,a,pair").is_none());
+            assert!(Location::parse("12.3").is_none());
+            assert!(Location::parse("45.0,-120.0").is_some());
+        }
+
+        #[test]
+        fn equinox_noon_elevation_is_ninety_minus_latitude() {
+            // At the equinox (N = 81, declination ≈ 0) and solar noon (hour angle 0)
+            // the elevation reduces to `90° − latitude`.
+            for latitude in [0.0, 23.5, 45.0, 60.0] {
+                let e = elevation(latitude, 0.0, 81.0, 12.0);
+                assert!((e - (90.0 - latitude)).abs() < 0.5, "lat {latitude}: {e}");
+            }
+        }
+
+        #[test]
+        fn temperature_clamps_and_interpolates() {
+            assert_eq!(temperature_from_elevation(DAY_ELEVATION), DAY_TEMPERATURE);
+            assert_eq!(temperature_from_elevation(45.0), DAY_TEMPERATURE);
+            assert_eq!(temperature_from_elevation(NIGHT_ELEVATION), NIGHT_TEMPERATURE);
+            assert_eq!(temperature_from_elevation(-30.0), NIGHT_TEMPERATURE);
+
+            let midpoint = (DAY_ELEVATION + NIGHT_ELEVATION) / 2.0;
+            let mid_temperature = temperature_from_elevation(midpoint);
+            assert!(mid_temperature > NIGHT_TEMPERATURE && mid_temperature < DAY_TEMPERATURE);
+            assert_eq!(mid_temperature, (NIGHT_TEMPERATURE + DAY_TEMPERATURE) / 2);
+        }
+    }
+}
+
+fn list_monitors(event_loop: &EventLoopWindowTarget<()>) -> Vec<MonitorHandle> {
+    let mut monitors = event_loop.available_monitors().collect::<Vec<_>>();
+    if let Some(primary_monitor) = event_loop.primary_monitor() {
+        if let Some(index) = monitors
+            .iter()
+            .position(|monitor| *monitor == primary_monitor)
+        {
+            let last = monitors.len() - 1;
+            monitors.swap(index, last);
+        }
+    }
+    monitors
+}
+
+/// The monitors that should currently be blanked: every monitor in dark mode,
+/// all but the primary in bright mode (a lone monitor is blanked in either mode).
+fn target_monitors(event_loop: &EventLoopWindowTarget<()>, dark: bool) -> Vec<MonitorHandle> {
+    let monitors = list_monitors(event_loop);
+    let count = if dark || monitors.len() < 2 {
+        monitors.len()
+    } else {
+        monitors.len() - 1
+    };
+    monitors.into_iter().take(count).collect()
+}
+
+struct Color {
+    temperature: u32,
+    dark: bool,
+    alpha: f64,
+}
+
+impl Color {
+    fn new(dark: bool) -> Self {
+        Self {
+            temperature: 5500,
+            dark,
+            alpha: 1.0,
+        }
+    }
+
+    /// The color the backend should paint, as linear `0.0..=1.0` RGBA channels.
+    fn rgba(&self) -> (f64, f64, f64, f64) {
+        if self.dark {
+            (0., 0., 0., self.alpha)
+        } else {
+            let (r, g, b) = tempergb::rgb_from_temperature(self.temperature).into();
+            (
+                f64::from(r) / 255.0,
+                f64::from(g) / 255.0,
+                f64::from(b) / 255.0,
+                self.alpha,
+            )
+        }
+    }
+
+    fn dim(&mut self) -> bool {
+        if self.alpha > MIN_ALPHA {
+            self.alpha = (self.alpha - ALPHA_STEP).max(MIN_ALPHA);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn undim(&mut self) -> bool {
+        if self.alpha < 1.0 {
+            self.alpha = (self.alpha + ALPHA_STEP).min(1.0);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn increase(&mut self) -> bool {
+        if self.temperature < 6600 {
+            self.temperature += 100;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn toggle(&mut self) {
+        self.dark = !self.dark;
+    }
+
+    fn set_dark(&mut self, dark: bool) {
+        self.dark = dark;
+    }
+
+    fn decrease(&mut self) -> bool {
+        if self.temperature > 1500 {
+            self.temperature -= 100;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn set_temperature(&mut self, temperature: u32) {
+        self.temperature = temperature.clamp(1500, 6600);
+    }
+}
+
+fn find(windows: &[Window], id: WindowId) -> Option<&Window> {
+    windows.iter().find(|window| window.id() == id)
+}
+
+/// Arms a `WaitUntil` wake for `deadline`, keeping whichever deadline is nearer
+/// when one is already scheduled. A pending `Poll` or `Exit` takes precedence and
+/// is left untouched, so independent timers can cooperate without one clobbering
+/// the other.
+fn schedule(control_flow: &mut ControlFlow, deadline: Instant) {
+    match *control_flow {
+        ControlFlow::Wait => *control_flow = ControlFlow::WaitUntil(deadline),
+        ControlFlow::WaitUntil(current) if deadline < current => {
+            *control_flow = ControlFlow::WaitUntil(deadline);
+        }
+        _ => {}
+    }
+}
+
+/// How Blank should paint when it starts.
+pub struct Config {
+    dark: bool,
+    location: Option<Location>,
+}
+
+impl Config {
+    /// A plain blanking configuration; `dark` picks opaque black over a warm color.
+    #[must_use]
+    pub fn new(dark: bool) -> Self {
+        Self {
+            dark,
+            location: None,
+        }
+    }
+
+    /// Enables the circadian `auto` schedule for `location`.
+    #[must_use]
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Parses the CLI arguments following the program name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message naming the unrecognized parameter when the first
+    /// argument is not one of `b`, `bright`, `d`, `dark`, `auto`, or absent.
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        match args.next().as_deref() {
+            Some("b" | "bright") => Ok(Self::new(false)),
+            Some("d" | "dark") | None => Ok(Self::new(true)),
+            Some("auto") => {
+                let location = args
+                    .next()
+                    .or_else(|| std::env::var("BLANK_LOCATION").ok())
+                    .and_then(|raw| Location::parse(&raw));
+                if location.is_none() {
+                    eprintln!(
+                        "No valid `lat,lon` given for `auto` (argument or $BLANK_LOCATION); \
+                         falling back to manual temperature"
+                    );
+                }
+                Ok(Self {
+                    dark: false,
+                    location,
+                })
+            }
+            Some(other) => Err(format!(
+                "Unrecognized parameter `{other}`. Expected `b`, `bright`, `d`, `dark`, `auto`, or none"
+            )),
+        }
+    }
+}
+
+/// A reusable screen blanker that other programs can drive from their own event loop.
+///
+/// Feed every winit event to [`Blanker::pump`] — whether from the blocking
+/// `run`, from `run_on_demand`, or from a `pump_events` tick — and call
+/// [`Blanker::start`]/[`Blanker::stop`] around the session to engage and release
+/// the no-sleep assertion. Temperature, dimming and dark/bright can be changed
+/// programmatically at any time.
+pub struct Blanker {
+    backend: PlatformBackend,
+    windows: Vec<Window>,
+    /// The monitor each live window was built for, so reconciliation and the
+    /// dedup/dismiss bookkeeping never depend on the racy live `current_monitor`.
+    owners: HashMap<WindowId, MonitorHandle>,
+    /// The monitor set seen at the last reconciliation; sync only reacts to the
+    /// difference against this rather than re-blanking every connected display.
+    known_monitors: Vec<MonitorHandle>,
+    /// Monitors the user manually un-blanked (Escape/Cmd+W); sync leaves these
+    /// alone until the display is physically disconnected and reconnected.
+    dismissed: Vec<MonitorHandle>,
+    color: Color,
+    dark: bool,
+    current_modifiers: ModifiersState,
+    released_a: bool,
+    released_w: bool,
+    released_q: bool,
+    graceful: bool,
+    suspended: bool,
+    cursor_timer: Option<(WindowId, Instant)>,
+    circadian: Option<(Location, Instant)>,
+    monitor_sync: Instant,
+    no_sleep: Option<nosleep::NoSleep>,
+}
+
+impl Blanker {
+    /// Creates a blanker in its initial state; no windows exist until the event
+    /// loop reports `Resumed` through [`Blanker::pump`].
+    #[must_use]
+    pub fn new(config: Config) -> Self {
+        let Config { dark, location } = config;
+        Self {
+            backend: PlatformBackend::new(),
+            windows: Vec::new(),
+            owners: HashMap::new(),
+            known_monitors: Vec::new(),
+            dismissed: Vec::new(),
+            color: Color::new(dark),
+            dark,
+            current_modifiers: ModifiersState::default(),
+            released_a: true,
+            released_w: true,
+            released_q: true,
+            graceful: false,
+            suspended: false,
+            cursor_timer: None,
+            circadian: location.map(|location| (location, Instant::now())),
+            monitor_sync: Instant::now() + MONITOR_SYNC_INTERVAL,
+            no_sleep: None,
+        }
+    }
+
+    /// Engages the no-sleep assertion that keeps the display awake while blanking.
+    pub fn start(&mut self) {
+        let mut no_sleep = nosleep::NoSleep::new().unwrap();
+        no_sleep
+            .start(NoSleepType::PreventUserIdleDisplaySleep)
+            .unwrap();
+        self.no_sleep = Some(no_sleep);
+    }
+
+    /// Tears down every window and releases the no-sleep assertion.
+    pub fn stop(&mut self) {
+        for window in &self.windows {
+            self.backend.forget(window.id());
+        }
+        self.windows.clear();
+        self.owners.clear();
+        self.known_monitors.clear();
+        self.dismissed.clear();
+        self.no_sleep = None;
+    }
+
+    /// Sets the color temperature directly (clamped to `1500..=6600` K).
+    pub fn set_temperature(&mut self, temperature: u32) {
+        self.color.set_temperature(temperature);
+        self.repaint();
+    }
+
+    /// Switches between opaque-black (`dark`) and warm-color blanking.
+    pub fn set_dark(&mut self, dark: bool) {
+        self.dark = dark;
+        self.color.set_dark(dark);
+        self.repaint();
+    }
+
+    /// Makes the overlay one step more transparent, returning whether it moved.
+    ///
+    /// Dimming is only supported on macOS; the `softbuffer` surface used on
+    /// X11/Wayland is always opaque, so this is a no-op returning `false` there.
+    pub fn dim(&mut self) -> bool {
+        if cfg!(not(target_os = "macos")) {
+            return false;
+        }
+        let changed = self.color.dim();
+        if changed {
+            self.repaint();
+        }
+        changed
+    }
+
+    /// Makes the overlay one step more opaque, returning whether it moved.
+    ///
+    /// As with [`Blanker::dim`], this is a no-op returning `false` off macOS.
+    pub fn undim(&mut self) -> bool {
+        if cfg!(not(target_os = "macos")) {
+            return false;
+        }
+        let changed = self.color.undim();
+        if changed {
+            self.repaint();
+        }
+        changed
+    }
+
+    fn repaint(&mut self) {
+        for window in &self.windows {
+            self.backend.fill(window, &self.color);
+        }
+    }
+
+    /// Builds a window for `monitor` and records it as the window's owner.
+    fn build_on(&mut self, event_loop: &EventLoopWindowTarget<()>, monitor: &MonitorHandle) {
+        let window = self.backend.build_window(event_loop, &self.color, monitor);
+        self.owners.insert(window.id(), monitor.clone());
+        self.windows.push(window);
+    }
+
+    /// Drops the window `id`, forgetting its backend and ownership state.
+    fn drop_window(&mut self, id: WindowId) -> bool {
+        if let Some(index) = self.windows.iter().position(|window| window.id() == id) {
+            self.windows.swap_remove(index);
+            self.owners.remove(&id);
+            self.backend.forget(id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Drops a window at the user's request, remembering its monitor so the
+    /// reconciler does not immediately re-blank that display.
+    fn dismiss(&mut self, id: WindowId) -> bool {
+        if let Some(monitor) = self.owners.get(&id).cloned() {
+            if !self.dismissed.contains(&monitor) {
+                self.dismissed.push(monitor);
+            }
+        }
+        self.drop_window(id)
+    }
+
+    /// Blanks the first connected monitor that has no window yet (Cmd+A),
+    /// clearing any dismissal for it.
+    fn add_window(&mut self, event_loop: &EventLoopWindowTarget<()>) {
+        let open = self.owners.values().cloned().collect::<Vec<_>>();
+        if let Some(monitor) = list_monitors(event_loop)
+            .into_iter()
+            .find(|monitor| !open.contains(monitor))
+        {
+            self.dismissed.retain(|seen| *seen != monitor);
+            self.build_on(event_loop, &monitor);
+        }
+    }
+
+    /// Blanks every target monitor and resets the reconciliation bookkeeping; used
+    /// when the loop (re)starts and no windows exist yet.
+    fn populate(&mut self, event_loop: &EventLoopWindowTarget<()>) {
+        for monitor in target_monitors(event_loop, self.dark) {
+            self.build_on(event_loop, &monitor);
+        }
+        self.known_monitors = list_monitors(event_loop);
+        self.dismissed.clear();
+    }
+
+    /// Reconciles windows with the monitors only on actual connect/disconnect,
+    /// diffing the live monitor set against the last-seen one. Windows the user
+    /// dismissed are not re-blanked, and disconnected monitors are torn down and
+    /// forgotten (so a later reconnect blanks them again).
+    fn sync_windows(&mut self, event_loop: &EventLoopWindowTarget<()>) {
+        let connected = list_monitors(event_loop);
+
+        let disconnected = self
+            .known_monitors
+            .iter()
+            .filter(|&monitor| !connected.contains(monitor))
+            .cloned()
+            .collect::<Vec<_>>();
+        for monitor in &disconnected {
+            let ids = self
+                .owners
+                .iter()
+                .filter(|(_, owner)| *owner == monitor)
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            for id in ids {
+                self.drop_window(id);
+            }
+            self.dismissed.retain(|seen| seen != monitor);
+        }
+
+        let targets = target_monitors(event_loop, self.dark);
+        let connected_targets = connected
+            .iter()
+            .filter(|&monitor| {
+                !self.known_monitors.contains(monitor)
+                    && targets.contains(monitor)
+                    && !self.dismissed.contains(monitor)
+                    && !self.owners.values().any(|owner| owner == monitor)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for monitor in &connected_targets {
+            self.build_on(event_loop, monitor);
+        }
+
+        self.known_monitors = connected;
+    }
+
+    /// Processes a single winit event, driving window lifecycle, keyboard control
+    /// and the circadian/monitor timers. Call this for every event the host loop
+    /// produces.
+    #[allow(clippy::too_many_lines)]
+    pub fn pump(
+        &mut self,
+        event: Event<'_, ()>,
+        event_loop: &EventLoopWindowTarget<()>,
+        control_flow: &mut ControlFlow,
+    ) {
+        if *control_flow == ControlFlow::Exit {
+            if let Event::NewEvents(winit::event::StartCause::Poll) = event {
+                if self.windows.is_empty() {
+                    if self.graceful {
+                        self.graceful = false;
+                    } else {
+                        panic!("Force exit");
+                    }
+                } else {
+                    self.windows.clear();
+                }
+            }
+        }
+        *control_flow = ControlFlow::Wait;
+
+        if let Some((window_id, instant)) = self.cursor_timer {
+            if instant.elapsed() >= Duration::from_secs(1) {
+                self.cursor_timer = None;
+                if let Some(window) = find(&self.windows, window_id) {
+                    window.set_cursor_visible(false);
+                }
+            } else {
+                *control_flow = ControlFlow::Poll;
+            }
+        }
+
+        if let Some((location, next)) = self.circadian.as_mut() {
+            if Instant::now() >= *next {
+                self.color.set_temperature(location.temperature());
+                for window in &self.windows {
+                    self.backend.fill(window, &self.color);
+                }
+                *next = Instant::now() + CIRCADIAN_INTERVAL;
+            }
+            schedule(control_flow, *next);
+        }
+
+        if !self.graceful && !self.suspended && *control_flow != ControlFlow::Exit {
+            if Instant::now() >= self.monitor_sync {
+                self.sync_windows(event_loop);
+                self.monitor_sync = Instant::now() + MONITOR_SYNC_INTERVAL;
+            }
+            schedule(control_flow, self.monitor_sync);
+        }
+
+        match event {
+            Event::Resumed => {
+                if self.windows.is_empty() && !self.graceful && *control_flow != ControlFlow::Exit {
+                    self.populate(event_loop);
+                    self.monitor_sync = Instant::now() + MONITOR_SYNC_INTERVAL;
+                }
+                self.suspended = false;
+            }
+            Event::Suspended => {
+                for window in &self.windows {
+                    self.backend.forget(window.id());
+                }
+                self.windows.clear();
+                self.owners.clear();
+                self.known_monitors.clear();
+                self.suspended = true;
+            }
+            Event::WindowEvent { event, window_id } => match event {
+                WindowEvent::CursorMoved { .. } => {
+                    if self.cursor_timer.filter(|(id, _)| *id == window_id).is_some() {
+                        self.cursor_timer = Some((window_id, Instant::now()));
+                    } else if let Some(window) = find(&self.windows, window_id) {
+                        window.focus_window();
+                        window.set_cursor_visible(true);
+                        self.cursor_timer = Some((window_id, Instant::now()));
+                    }
+                }
+                WindowEvent::CloseRequested => {
+                    if self.dismiss(window_id) && self.windows.is_empty() {
+                        self.graceful = true;
+                        *control_flow = ControlFlow::Exit;
+                    }
+                }
+                WindowEvent::ScaleFactorChanged { .. } => {
+                    if let Some(window) = find(&self.windows, window_id) {
+                        if let Some(monitor) = window.current_monitor() {
+                            let (position, size) = window_geometry(&monitor);
+                            window.set_outer_position(position);
+                            window.set_inner_size(size);
+                        }
+                        self.backend.fill(window, &self.color);
+                    }
+                }
+                WindowEvent::ModifiersChanged(modifiers) => {
+                    self.current_modifiers = modifiers;
+                }
+                WindowEvent::ReceivedCharacter('=') => {
+                    if self.color.increase() {
+                        self.repaint();
+                    }
+                }
+                WindowEvent::ReceivedCharacter('-') => {
+                    if self.color.decrease() {
+                        self.repaint();
+                    }
+                }
+                WindowEvent::ReceivedCharacter('[') => {
+                    self.dim();
+                }
+                WindowEvent::ReceivedCharacter(']') => {
+                    self.undim();
+                }
+                WindowEvent::ReceivedCharacter('b') => {
+                    self.color.toggle();
+                    self.repaint();
+                }
+                WindowEvent::KeyboardInput {
+                    input:
+                        KeyboardInput {
+                            virtual_keycode: Some(virtual_code),
+                            state,
+                            ..
+                        },
+                    ..
+                } => match (virtual_code, state) {
+                    (VirtualKeyCode::Escape, ElementState::Released) => {
+                        if self.dismiss(window_id) && self.windows.is_empty() {
+                            self.graceful = true;
+                            *control_flow = ControlFlow::Exit;
+                        }
+                    }
+                    (VirtualKeyCode::A, ElementState::Released) => {
+                        self.released_a = true;
+                    }
+                    (VirtualKeyCode::A, ElementState::Pressed)
+                        if self.released_a && self.current_modifiers == ModifiersState::LOGO =>
+                    {
+                        self.released_a = false;
+                        self.add_window(event_loop);
+                    }
+                    (VirtualKeyCode::W, ElementState::Released) => {
+                        self.released_w = true;
+                    }
+                    (VirtualKeyCode::W, ElementState::Pressed)
+                        if self.released_w && self.current_modifiers == ModifiersState::LOGO =>
+                    {
+                        self.released_w = false;
+                        if self.dismiss(window_id) && self.windows.is_empty() {
+                            self.graceful = true;
+                            *control_flow = ControlFlow::Exit;
+                        }
+                    }
+                    (VirtualKeyCode::Q, ElementState::Released) => {
+                        self.released_q = true;
+                    }
+                    (VirtualKeyCode::Q, ElementState::Pressed)
+                        if self.released_q && self.current_modifiers == ModifiersState::LOGO =>
+                    {
+                        self.released_q = false;
+                        *control_flow = ControlFlow::Exit;
+                    }
+                    _ => (),
+                },
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+}